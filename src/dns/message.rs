@@ -0,0 +1,322 @@
+use std::fmt::Display;
+
+use nom::bits::bits;
+use nom::bits::complete::take as take_bits;
+use nom::number::complete::be_u16;
+use nom::sequence::tuple;
+use nom::Finish;
+
+use super::parse_utils::VResult;
+use super::record::Record;
+use super::{Buffer, DeSerialize, QClass, QType, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub id: u16,
+    pub qr: bool,
+    pub opcode: u8,
+    pub aa: bool,
+    pub tc: bool,
+    pub rd: bool,
+    pub ra: bool,
+    pub rcode: u8,
+    pub qd_count: u16,
+    pub an_count: u16,
+    pub ns_count: u16,
+    pub ar_count: u16,
+}
+
+impl Header {
+    fn query(id: u16) -> Self {
+        Self {
+            id,
+            qr: false,
+            opcode: 0,
+            aa: false,
+            tc: false,
+            rd: true,
+            ra: false,
+            rcode: 0,
+            qd_count: 1,
+            an_count: 0,
+            ns_count: 0,
+            ar_count: 0,
+        }
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "id: {} qr: {} opcode: {} aa: {} tc: {} rd: {} ra: {} rcode: {}\nqdcount: {} ancount: {} nscount: {} arcount: {}",
+            self.id,
+            self.qr,
+            self.opcode,
+            self.aa,
+            self.tc,
+            self.rd,
+            self.ra,
+            self.rcode,
+            self.qd_count,
+            self.an_count,
+            self.ns_count,
+            self.ar_count,
+        )
+    }
+}
+
+fn parse_header(buffer: &[u8]) -> VResult<&[u8], Header> {
+    let (buffer, id) = be_u16(buffer)?;
+
+    type BitError<'a> = nom::error::Error<(&'a [u8], usize)>;
+
+    let (buffer, (qr, opcode, aa, tc, rd, ra, _z, rcode)): (
+        &[u8],
+        (u8, u8, u8, u8, u8, u8, u8, u8),
+    ) = bits(tuple((
+        take_bits::<_, u8, usize, BitError>(1usize),
+        take_bits::<_, u8, usize, BitError>(4usize),
+        take_bits::<_, u8, usize, BitError>(1usize),
+        take_bits::<_, u8, usize, BitError>(1usize),
+        take_bits::<_, u8, usize, BitError>(1usize),
+        take_bits::<_, u8, usize, BitError>(1usize),
+        take_bits::<_, u8, usize, BitError>(3usize),
+        take_bits::<_, u8, usize, BitError>(4usize),
+    )))(buffer)?;
+
+    let (buffer, (qd_count, an_count, ns_count, ar_count)) =
+        tuple((be_u16, be_u16, be_u16, be_u16))(buffer)?;
+
+    Ok((
+        buffer,
+        Header {
+            id,
+            qr: qr == 1,
+            opcode,
+            aa: aa == 1,
+            tc: tc == 1,
+            rd: rd == 1,
+            ra: ra == 1,
+            rcode,
+            qd_count,
+            an_count,
+            ns_count,
+            ar_count,
+        },
+    ))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    pub qname: String,
+    pub qtype: QType,
+    pub qclass: QClass,
+}
+
+fn parse_question<'a>(buffer: &'a [u8], source: &'a [u8]) -> VResult<&'a [u8], Question> {
+    let mut labels = Vec::new();
+    let (buffer, qname) = super::parse_utils::parse_names(buffer, source, &mut labels)?;
+    let (buffer, qtype) = super::parse_utils::parse_qtype(buffer)?;
+    let (buffer, qclass) = super::parse_utils::parse_qclass(buffer)?;
+
+    Ok((
+        buffer,
+        Question {
+            qname,
+            qtype,
+            qclass,
+        },
+    ))
+}
+
+/// The EDNS(0) (RFC 6891) options a query advertises: the receiver's UDP
+/// payload size and whether the DNSSEC OK (DO) bit is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdnsOpt {
+    pub udp_size: u16,
+    pub dnssec: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub header: Header,
+    pub question: Question,
+    pub records: Vec<Record>,
+    pub authorities: Vec<Record>,
+    pub additionals: Vec<Record>,
+    pub edns: Option<EdnsOpt>,
+}
+
+impl Message {
+    pub(crate) fn new(domain: &str, qtype: QType) -> Self {
+        Self {
+            header: Header::query(1),
+            question: Question {
+                qname: domain.to_owned(),
+                qtype,
+                qclass: QClass::IN,
+            },
+            records: Vec::new(),
+            authorities: Vec::new(),
+            additionals: Vec::new(),
+            edns: None,
+        }
+    }
+
+    /// Advertises EDNS(0) support by attaching an OPT pseudo-record to the
+    /// additional section on serialize.
+    pub fn with_edns(mut self, udp_size: u16, dnssec: bool) -> Self {
+        self.edns = Some(EdnsOpt { udp_size, dnssec });
+        self.header.ar_count = 1;
+        self
+    }
+
+    pub fn a(domain: &str) -> Self {
+        Self::new(domain, QType::A)
+    }
+
+    pub fn aaaa(domain: &str) -> Self {
+        Self::new(domain, QType::AAAA)
+    }
+
+    pub fn cname(domain: &str) -> Self {
+        Self::new(domain, QType::CNAME)
+    }
+
+    pub fn ns(domain: &str) -> Self {
+        Self::new(domain, QType::NS)
+    }
+
+    pub fn mx(domain: &str) -> Self {
+        Self::new(domain, QType::MX)
+    }
+
+    pub fn soa(domain: &str) -> Self {
+        Self::new(domain, QType::SOA)
+    }
+
+    pub fn txt(domain: &str) -> Self {
+        Self::new(domain, QType::TXT)
+    }
+
+    pub fn ptr(domain: &str) -> Self {
+        Self::new(domain, QType::PTR)
+    }
+
+    pub fn srv(domain: &str) -> Self {
+        Self::new(domain, QType::SRV)
+    }
+
+    pub fn caa(domain: &str) -> Self {
+        Self::new(domain, QType::CAA)
+    }
+
+    /// Looks for an OPT pseudo-record in the additional section and, if
+    /// found, returns the server's negotiated udp payload size and whether
+    /// the DNSSEC OK (DO) bit was echoed back.
+    pub fn negotiated_edns(&self) -> Option<EdnsOpt> {
+        self.additionals.iter().find_map(|record| match &record.rdata {
+            super::record::RData::OPT { udp_size, flags } => Some(EdnsOpt {
+                udp_size: *udp_size,
+                dnssec: flags & super::record::DO_BIT != 0,
+            }),
+            _ => None,
+        })
+    }
+}
+
+fn serialize_qname(qname: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    for label in qname.split('.') {
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+    out
+}
+
+impl Serialize for Message {
+    fn serialize(&self) -> anyhow::Result<Vec<u8>> {
+        let mut out = Vec::new();
+
+        out.extend_from_slice(&self.header.id.to_be_bytes());
+
+        let flags: u16 = ((self.header.qr as u16) << 15)
+            | ((self.header.opcode as u16) << 11)
+            | ((self.header.aa as u16) << 10)
+            | ((self.header.tc as u16) << 9)
+            | ((self.header.rd as u16) << 8)
+            | ((self.header.ra as u16) << 7)
+            | (self.header.rcode as u16);
+        out.extend_from_slice(&flags.to_be_bytes());
+
+        out.extend_from_slice(&self.header.qd_count.to_be_bytes());
+        out.extend_from_slice(&self.header.an_count.to_be_bytes());
+        out.extend_from_slice(&self.header.ns_count.to_be_bytes());
+        out.extend_from_slice(&self.header.ar_count.to_be_bytes());
+
+        out.extend(serialize_qname(&self.question.qname));
+        out.extend_from_slice(&u16::from(self.question.qtype).to_be_bytes());
+        out.extend_from_slice(&u16::from(self.question.qclass).to_be_bytes());
+
+        if let Some(edns) = self.edns {
+            out.extend(serialize_opt(edns));
+        }
+
+        Ok(out)
+    }
+}
+
+fn serialize_opt(edns: EdnsOpt) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.push(0); // root NAME
+    out.extend_from_slice(&u16::from(QType::OPT).to_be_bytes());
+    out.extend_from_slice(&edns.udp_size.to_be_bytes());
+
+    let flags: u32 = if edns.dnssec { super::record::DO_BIT } else { 0 };
+    out.extend_from_slice(&flags.to_be_bytes());
+
+    out.extend_from_slice(&0u16.to_be_bytes()); // RDLENGTH
+    out
+}
+
+fn deserialize_records<'a>(buffer: &mut Buffer<'a>, count: u16) -> anyhow::Result<Vec<Record>> {
+    let mut records = Vec::with_capacity(count as usize);
+    for _ in 0..count {
+        let record = Record::deserialize(buffer)?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+impl<'a> DeSerialize<'a> for Message {
+    type Item = Message;
+
+    fn deserialize(buffer: &mut Buffer<'a>) -> anyhow::Result<Self::Item> {
+        let (rest, header) = parse_header(buffer.current).finish().map_err(|e| {
+            anyhow::Error::msg(format!("Error at: {:?}, with code: {:?}", e.input, e.code))
+        })?;
+
+        let (rest, question) = parse_question(rest, buffer.source)
+            .finish()
+            .map_err(|e| {
+                anyhow::Error::msg(format!("Error at: {:?}, with code: {:?}", e.input, e.code))
+            })?;
+
+        buffer.current = rest;
+
+        let records = deserialize_records(buffer, header.an_count)?;
+        let authorities = deserialize_records(buffer, header.ns_count)?;
+        let additionals = deserialize_records(buffer, header.ar_count)?;
+
+        Ok(Message {
+            header,
+            question,
+            records,
+            authorities,
+            additionals,
+            edns: None,
+        })
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+pub mod message;
+pub mod parse_utils;
+pub mod proxy;
+pub mod record;
+pub mod resolver;
+pub mod transport;
+
+pub use record::Record;
+
+/// A cursor over an in-flight DNS message.
+///
+/// `current` is the slice that parsing should continue from, while `source`
+/// always points at the start of the whole message so that name-compression
+/// pointers (which are offsets from the start of the message) can be
+/// resolved regardless of how far `current` has advanced.
+#[derive(Debug, Clone, Copy)]
+pub struct Buffer<'a> {
+    pub current: &'a [u8],
+    pub source: &'a [u8],
+}
+
+pub trait Serialize {
+    fn serialize(&self) -> Result<Vec<u8>>;
+}
+
+pub trait DeSerialize<'a> {
+    type Item;
+
+    fn deserialize(buffer: &mut Buffer<'a>) -> Result<Self::Item>;
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QType {
+    A,
+    NS,
+    CNAME,
+    SOA,
+    PTR,
+    MX,
+    TXT,
+    AAAA,
+    SRV,
+    OPT,
+    CAA,
+    Other(u16),
+}
+
+impl From<u16> for QType {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => QType::A,
+            2 => QType::NS,
+            5 => QType::CNAME,
+            6 => QType::SOA,
+            12 => QType::PTR,
+            15 => QType::MX,
+            16 => QType::TXT,
+            28 => QType::AAAA,
+            33 => QType::SRV,
+            41 => QType::OPT,
+            257 => QType::CAA,
+            other => QType::Other(other),
+        }
+    }
+}
+
+impl From<QType> for u16 {
+    fn from(value: QType) -> Self {
+        match value {
+            QType::A => 1,
+            QType::NS => 2,
+            QType::CNAME => 5,
+            QType::SOA => 6,
+            QType::PTR => 12,
+            QType::MX => 15,
+            QType::TXT => 16,
+            QType::AAAA => 28,
+            QType::SRV => 33,
+            QType::OPT => 41,
+            QType::CAA => 257,
+            QType::Other(code) => code,
+        }
+    }
+}
+
+impl Display for QType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QType::A => write!(f, "A"),
+            QType::NS => write!(f, "NS"),
+            QType::CNAME => write!(f, "CNAME"),
+            QType::SOA => write!(f, "SOA"),
+            QType::PTR => write!(f, "PTR"),
+            QType::MX => write!(f, "MX"),
+            QType::TXT => write!(f, "TXT"),
+            QType::AAAA => write!(f, "AAAA"),
+            QType::SRV => write!(f, "SRV"),
+            QType::OPT => write!(f, "OPT"),
+            QType::CAA => write!(f, "CAA"),
+            QType::Other(code) => write!(f, "TYPE{code}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QClass {
+    IN,
+    CS,
+    CH,
+    HS,
+    Other(u16),
+}
+
+impl From<u16> for QClass {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => QClass::IN,
+            2 => QClass::CS,
+            3 => QClass::CH,
+            4 => QClass::HS,
+            other => QClass::Other(other),
+        }
+    }
+}
+
+impl From<QClass> for u16 {
+    fn from(value: QClass) -> Self {
+        match value {
+            QClass::IN => 1,
+            QClass::CS => 2,
+            QClass::CH => 3,
+            QClass::HS => 4,
+            QClass::Other(code) => code,
+        }
+    }
+}
+
+impl Display for QClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QClass::IN => write!(f, "IN"),
+            QClass::CS => write!(f, "CS"),
+            QClass::CH => write!(f, "CH"),
+            QClass::HS => write!(f, "HS"),
+            QClass::Other(code) => write!(f, "CLASS{code}"),
+        }
+    }
+}
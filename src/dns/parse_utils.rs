@@ -0,0 +1,165 @@
+use nom::bytes::complete::take;
+use nom::number::complete::{be_u16, be_u32, be_u8};
+use nom::IResult;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+use super::{QClass, QType};
+
+pub type VResult<I, O> = IResult<I, O, nom::error::Error<I>>;
+
+pub fn parse_qtype(buffer: &[u8]) -> VResult<&[u8], QType> {
+    let (buffer, code) = be_u16(buffer)?;
+    Ok((buffer, QType::from(code)))
+}
+
+pub fn parse_qclass(buffer: &[u8]) -> VResult<&[u8], QClass> {
+    let (buffer, code) = be_u16(buffer)?;
+    Ok((buffer, QClass::from(code)))
+}
+
+pub fn parse_ttl(buffer: &[u8]) -> VResult<&[u8], Duration> {
+    let (buffer, seconds) = be_u32(buffer)?;
+    Ok((buffer, Duration::from_secs(seconds as u64)))
+}
+
+pub fn parse_rdlength(buffer: &[u8]) -> VResult<&[u8], u16> {
+    be_u16(buffer)
+}
+
+pub fn parse_ipv4(buffer: &[u8]) -> VResult<&[u8], Ipv4Addr> {
+    let (buffer, bytes) = take(4usize)(buffer)?;
+    Ok((buffer, Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])))
+}
+
+pub fn parse_ipv6(buffer: &[u8]) -> VResult<&[u8], Ipv6Addr> {
+    let (buffer, bytes) = take(16usize)(buffer)?;
+    let mut octets = [0u8; 16];
+    octets.copy_from_slice(bytes);
+    Ok((buffer, Ipv6Addr::from(octets)))
+}
+
+pub fn take_token(buffer: &[u8], len: usize) -> VResult<&[u8], &str> {
+    let (buffer, bytes) = take(len)(buffer)?;
+    let token = std::str::from_utf8(bytes).unwrap_or_default();
+    Ok((buffer, token))
+}
+
+pub fn take_bytes(buffer: &[u8], len: usize) -> VResult<&[u8], Vec<u8>> {
+    let (buffer, bytes) = take(len)(buffer)?;
+    Ok((buffer, bytes.to_vec()))
+}
+
+const POINTER_MASK: u8 = 0xC0;
+
+// A full name is at most 255 octets on the wire (RFC 1035 section 3.1); we
+// bound the assembled dotted string at the same length.
+const MAX_NAME_LENGTH: usize = 255;
+
+// Compression pointers must point strictly backwards, so the number of
+// jumps a single name can make is bounded by the message size. 128 is far
+// more than any real message needs and keeps a malformed response from
+// burning unbounded time even on a maximum-size TCP message.
+const MAX_POINTER_JUMPS: usize = 128;
+
+fn pointer_error<I>(input: I) -> nom::Err<nom::error::Error<I>> {
+    nom::Err::Failure(nom::error::Error::new(input, nom::error::ErrorKind::Verify))
+}
+
+/// Parses a (possibly compressed) DNS name out of `buffer`.
+///
+/// `source` is the start of the whole message, since compression pointers
+/// are offsets from there rather than from `buffer`. `visited` collects the
+/// labels seen so far across recursive pointer jumps.
+///
+/// A pointer is only ever allowed to jump strictly backwards in `source`, so
+/// self-referential and forward-pointing pointers (and therefore any cycle)
+/// are rejected rather than followed, and the number of jumps is additionally
+/// capped so a long chain of distinct backward pointers can't stall parsing.
+pub fn parse_names<'a>(
+    buffer: &'a [u8],
+    source: &'a [u8],
+    visited: &mut Vec<String>,
+) -> VResult<&'a [u8], String> {
+    let mut cursor = buffer;
+    let mut furthest_offset = source.len() - buffer.len();
+    let mut jumps = 0usize;
+    let mut name_len = 0usize;
+    let mut first_pointer_rest = None;
+
+    loop {
+        let (rest, len) = be_u8(cursor)?;
+
+        if len == 0 {
+            let end = first_pointer_rest.unwrap_or(rest);
+            return Ok((end, visited.join(".")));
+        }
+
+        if len & POINTER_MASK == POINTER_MASK {
+            let (rest, lower) = be_u8(rest)?;
+            let offset = (((len & !POINTER_MASK) as usize) << 8) | lower as usize;
+
+            if first_pointer_rest.is_none() {
+                first_pointer_rest = Some(rest);
+            }
+
+            jumps += 1;
+            if jumps > MAX_POINTER_JUMPS || offset >= furthest_offset || offset >= source.len() {
+                return Err(pointer_error(cursor));
+            }
+
+            furthest_offset = offset;
+            cursor = &source[offset..];
+            continue;
+        }
+
+        let (rest, label) = take_token(rest, len as usize)?;
+        name_len += label.len() + 1;
+        if name_len > MAX_NAME_LENGTH {
+            return Err(pointer_error(cursor));
+        }
+
+        visited.push(label.to_owned());
+        cursor = rest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_self_referential_pointer() {
+        // A pointer at offset 0 that points right back at itself.
+        let raw = vec![0xC0, 0x00];
+        let mut visited = Vec::new();
+
+        let result = parse_names(&raw, &raw, &mut visited);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_forward_pointing_pointer() {
+        // A pointer at offset 0 pointing at offset 2, which is further into
+        // the buffer than the pointer itself.
+        let raw = vec![0xC0, 0x02, 0x00];
+        let mut visited = Vec::new();
+
+        let result = parse_names(&raw, &raw, &mut visited);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn follows_a_backward_pointer() {
+        let raw = vec![
+            0x06, b'g', b'o', b'o', b'g', b'l', b'e', 0x03, b'c', b'o', b'm', 0x00, 0xC0, 0x00,
+        ];
+        let mut visited = Vec::new();
+
+        let (_, name) = parse_names(&raw[12..], &raw, &mut visited).unwrap();
+
+        assert_eq!(name, "google.com");
+    }
+}
@@ -0,0 +1,105 @@
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use chrono::Local;
+use crossterm::terminal::disable_raw_mode;
+use tokio::net::UdpSocket;
+use tokio::signal;
+use tokio::time::timeout;
+
+use super::message::Message;
+use super::{transport, Buffer, DeSerialize};
+
+/// How long to wait for the upstream resolver before giving up on a single
+/// forwarded query. A slow or dead upstream must not stall the whole proxy.
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Turns `who` into a local DNS front-end: every UDP query received on
+/// `bind_addr` is logged, forwarded to `upstream`, and the answer relayed
+/// back to the original client. Runs until interrupted with Ctrl-C.
+pub async fn listen(bind_addr: &str, upstream: &str) -> Result<()> {
+    let sock = UdpSocket::bind(bind_addr)
+        .await
+        .with_context(|| format!("could not bind to {bind_addr}"))?;
+
+    println!("who listen: forwarding {bind_addr} -> {upstream} (ctrl-c to stop)");
+
+    let mut buffer = vec![0; 4096];
+    loop {
+        tokio::select! {
+            _ = signal::ctrl_c() => {
+                println!("shutting down");
+                return Ok(());
+            }
+            received = sock.recv_from(&mut buffer) => {
+                // A transient I/O error on one query must not take down a
+                // long-running front-end; log it and keep serving.
+                let (len, client) = match received {
+                    Ok(ok) => ok,
+                    Err(e) => {
+                        eprintln!("failed to receive query: {e}");
+                        continue;
+                    }
+                };
+                let query = buffer[..len].to_vec();
+
+                handle_query(&sock, client, query, upstream).await;
+            }
+        }
+    }
+}
+
+async fn handle_query(sock: &UdpSocket, client: SocketAddr, query: Vec<u8>, upstream: &str) {
+    let start = Instant::now();
+    let reply = match timeout(UPSTREAM_TIMEOUT, transport::query(&query, upstream, false)).await {
+        Ok(Ok(reply)) => reply,
+        Ok(Err(e)) => {
+            eprintln!("{client}: upstream query failed: {e}");
+            return;
+        }
+        Err(_) => {
+            eprintln!("{client}: upstream query timed out");
+            return;
+        }
+    };
+    let elapsed = start.elapsed();
+
+    if let Err(e) = sock.send_to(&reply, client).await {
+        eprintln!("{client}: failed to relay response: {e}");
+    }
+
+    let mut reply_buffer = Buffer {
+        current: &reply,
+        source: &reply,
+    };
+    match Message::deserialize(&mut reply_buffer) {
+        Ok(message) => {
+            let stats = crate::Statistics {
+                query_time: elapsed,
+                msg_sent: query.len(),
+                msg_rcvd: reply.len(),
+                current_time: Local::now(),
+                server: upstream.to_owned(),
+                edns: message.negotiated_edns(),
+                trace: Vec::new(),
+            };
+            if let Err(e) = display(&message, &stats) {
+                eprintln!("{client}: failed to render response: {e}");
+            }
+        }
+        Err(e) => eprintln!("{client}: failed to parse upstream response: {e}"),
+    }
+}
+
+/// Draws a single received query/answer through the same inline TUI the
+/// one-shot query path uses, so `who listen` reuses render_app instead of
+/// printing raw text.
+fn display(message: &Message, stats: &crate::Statistics) -> Result<()> {
+    let mut terminal =
+        crate::setup_terminal(message.header.qd_count, message.header.an_count, 0)?;
+    terminal.draw(|f| crate::render_app(f, message, stats))?;
+    disable_raw_mode().context("failed to disable raw mode")?;
+    let _ = terminal.show_cursor();
+    Ok(())
+}
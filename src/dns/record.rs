@@ -1,5 +1,6 @@
 use nom::number::complete::be_u16;
 use nom::number::complete::be_u32;
+use nom::number::complete::be_u8;
 use nom::sequence::tuple;
 use nom::Finish;
 use std::fmt::Display;
@@ -14,6 +15,7 @@ use super::parse_utils::parse_qclass;
 use super::parse_utils::parse_qtype;
 use super::parse_utils::parse_rdlength;
 use super::parse_utils::parse_ttl;
+use super::parse_utils::take_bytes;
 use super::parse_utils::take_token;
 use super::parse_utils::VResult;
 use super::Buffer;
@@ -40,6 +42,33 @@ pub enum RData {
         expire: u32,
         minimum: u32,
     },
+    PTR(String),
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    CAA {
+        flags: u8,
+        tag: String,
+        value: String,
+    },
+    // EDNS(0) pseudo-record (RFC 6891). CLASS carries the advertised udp
+    // payload size and TTL carries extended-rcode/version/flags, so neither
+    // has its usual meaning here.
+    OPT {
+        udp_size: u16,
+        flags: u32,
+    },
+    // A record type we don't have a dedicated parser for yet. We still have
+    // to consume exactly `rd_length` bytes so the rest of the message stays
+    // aligned, so we keep the raw RDATA around for a hex dump instead of
+    // discarding it.
+    Unknown {
+        qtype: u16,
+        data: Vec<u8>,
+    },
 }
 
 impl Display for RData {
@@ -66,10 +95,29 @@ impl Display for RData {
                 f,
                 "{mname}, {rname}, {serial}, {refresh}, {retry}, {expire}, {minimum}"
             ),
+            RData::PTR(value) => write!(f, "{value}"),
+            RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{priority} {weight} {port} {target}"),
+            RData::CAA { flags, tag, value } => write!(f, "{flags} {tag} {value}"),
+            RData::OPT { udp_size, flags } => {
+                write!(f, "udp_size={udp_size} do={}", flags & DO_BIT != 0)
+            }
+            RData::Unknown { qtype, data } => write!(f, "TYPE{qtype} {}", to_hex(data)),
         }
     }
 }
 
+// Bit 15 of the EDNS(0) extended TTL field: the DNSSEC OK (DO) bit.
+pub(crate) const DO_BIT: u32 = 0x0000_8000;
+
+pub(crate) fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ")
+}
+
 // Resource record format
 //
 // The answer, authority, and additional sections all share the same
@@ -207,7 +255,58 @@ fn parse_record<'a>(buffer: &'a [u8], source: &'a [u8]) -> VResult<&'a [u8], Rec
                 },
             )
         }
-        _ => unimplemented!(),
+        QType::PTR => {
+            let mut t = Vec::new();
+            let (buffer, name) = parse_names(buffer, source, &mut t)?;
+            (buffer, RData::PTR(name))
+        }
+        QType::SRV => {
+            let (buffer, (priority, weight, port)) = tuple((be_u16, be_u16, be_u16))(buffer)?;
+            let mut t = Vec::new();
+            let (buffer, target) = parse_names(buffer, source, &mut t)?;
+            (
+                buffer,
+                RData::SRV {
+                    priority,
+                    weight,
+                    port,
+                    target,
+                },
+            )
+        }
+        QType::OPT => {
+            // CLASS and TTL are repurposed by EDNS(0); recover their raw
+            // wire values instead of treating them as a QClass/lifetime.
+            let udp_size = u16::from(qclass);
+            let flags = ttl.as_secs() as u32;
+            let (buffer, _options) = take_bytes(buffer, rd_length.into())?;
+            (buffer, RData::OPT { udp_size, flags })
+        }
+        QType::CAA => {
+            let (buffer, flags) = be_u8(buffer)?;
+            let (buffer, tag_len) = be_u8(buffer)?;
+            let (buffer, tag) = take_token(buffer, tag_len.into())?;
+            let value_len = (rd_length as usize).saturating_sub(2 + tag_len as usize);
+            let (buffer, value) = take_token(buffer, value_len)?;
+            (
+                buffer,
+                RData::CAA {
+                    flags,
+                    tag: tag.to_owned(),
+                    value: value.to_owned(),
+                },
+            )
+        }
+        _ => {
+            let (buffer, data) = take_bytes(buffer, rd_length.into())?;
+            (
+                buffer,
+                RData::Unknown {
+                    qtype: qtype.into(),
+                    data,
+                },
+            )
+        }
     };
 
     Ok((
@@ -217,16 +316,16 @@ fn parse_record<'a>(buffer: &'a [u8], source: &'a [u8]) -> VResult<&'a [u8], Rec
 }
 
 impl<'a> DeSerialize<'a> for Record {
-    type Item = (&'a mut Buffer<'a>, Record);
+    type Item = Record;
 
-    fn deserialize(buffer: &'a mut Buffer<'a>) -> Result<Self::Item, anyhow::Error> {
+    fn deserialize(buffer: &mut Buffer<'a>) -> Result<Self::Item, anyhow::Error> {
         let (buf, record) = parse_record(buffer.current, buffer.source)
             .finish()
             .map_err(|e| {
                 anyhow::Error::msg(format!("Error at: {:?}, with code: {:?}", e.input, e.code))
             })?;
         buffer.current = buf;
-        Ok((buffer, record))
+        Ok(record)
     }
 }
 
@@ -260,7 +359,7 @@ mod tests {
             current: &raw,
             source: &raw,
         };
-        let (_, actual) = Record::deserialize(&mut buffer).unwrap();
+        let actual = Record::deserialize(&mut buffer).unwrap();
 
         let expected = Record::new(
             "google.com".to_owned(),
@@ -273,4 +372,122 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn parse_unsupported_record_type_as_hex_dump() {
+        let raw = vec![
+            0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00, 0x63,
+            0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x02, 0xab, 0xcd,
+        ];
+
+        let mut buffer = Buffer {
+            current: &raw,
+            source: &raw,
+        };
+        let actual = Record::deserialize(&mut buffer).unwrap();
+
+        let expected = Record::new(
+            "google.com".to_owned(),
+            QType::Other(99),
+            QClass::IN,
+            Duration::new(3600, 0),
+            2,
+            RData::Unknown {
+                qtype: 99,
+                data: vec![0xab, 0xcd],
+            },
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_ptr_record() {
+        let raw = vec![
+            0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00,
+            0x0c, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x12, 0x04, 0x68, 0x6f, 0x73,
+            0x74, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d,
+            0x00,
+        ];
+
+        let mut buffer = Buffer {
+            current: &raw,
+            source: &raw,
+        };
+        let actual = Record::deserialize(&mut buffer).unwrap();
+
+        let expected = Record::new(
+            "google.com".to_owned(),
+            QType::PTR,
+            QClass::IN,
+            Duration::new(3600, 0),
+            18,
+            RData::PTR("host.example.com".to_owned()),
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_srv_record() {
+        let raw = vec![
+            0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x00,
+            0x21, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x17, 0x00, 0x0a, 0x00, 0x14,
+            0x13, 0xc4, 0x03, 0x73, 0x69, 0x70, 0x07, 0x65, 0x78, 0x61, 0x6d, 0x70, 0x6c,
+            0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00,
+        ];
+
+        let mut buffer = Buffer {
+            current: &raw,
+            source: &raw,
+        };
+        let actual = Record::deserialize(&mut buffer).unwrap();
+
+        let expected = Record::new(
+            "google.com".to_owned(),
+            QType::SRV,
+            QClass::IN,
+            Duration::new(3600, 0),
+            23,
+            RData::SRV {
+                priority: 10,
+                weight: 20,
+                port: 5060,
+                target: "sip.example.com".to_owned(),
+            },
+        );
+
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn parse_caa_record() {
+        let raw = vec![
+            0x06, 0x67, 0x6f, 0x6f, 0x67, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d, 0x00, 0x01,
+            0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x16, 0x00, 0x05, 0x69, 0x73,
+            0x73, 0x75, 0x65, 0x6c, 0x65, 0x74, 0x73, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70,
+            0x74, 0x2e, 0x6f, 0x72, 0x67,
+        ];
+
+        let mut buffer = Buffer {
+            current: &raw,
+            source: &raw,
+        };
+        let actual = Record::deserialize(&mut buffer).unwrap();
+
+        let expected = Record::new(
+            "google.com".to_owned(),
+            QType::CAA,
+            QClass::IN,
+            Duration::new(3600, 0),
+            22,
+            RData::CAA {
+                flags: 0,
+                tag: "issue".to_owned(),
+                value: "letsencrypt.org".to_owned(),
+            },
+        );
+
+        assert_eq!(expected, actual);
+    }
 }
@@ -0,0 +1,171 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use anyhow::{anyhow, Result};
+
+use super::message::Message;
+use super::record::RData;
+use super::{transport, Buffer, DeSerialize, QType, Serialize};
+
+/// Bounds the number of delegation hops a trace will follow before giving up
+/// on what would otherwise be an infinite (or malicious) referral chain.
+const MAX_HOPS: usize = 16;
+
+/// IPv4 addresses of the 13 root name servers, `a.root-servers.net` through
+/// `m.root-servers.net`.
+const ROOT_SERVERS: &[Ipv4Addr] = &[
+    Ipv4Addr::new(198, 41, 0, 4),
+    Ipv4Addr::new(199, 9, 14, 201),
+    Ipv4Addr::new(192, 33, 4, 12),
+    Ipv4Addr::new(199, 7, 91, 13),
+    Ipv4Addr::new(192, 203, 230, 10),
+    Ipv4Addr::new(192, 5, 5, 241),
+    Ipv4Addr::new(192, 112, 36, 4),
+    Ipv4Addr::new(198, 97, 190, 53),
+    Ipv4Addr::new(192, 36, 148, 17),
+    Ipv4Addr::new(192, 58, 128, 30),
+    Ipv4Addr::new(193, 0, 14, 129),
+    Ipv4Addr::new(199, 7, 83, 42),
+    Ipv4Addr::new(202, 12, 27, 33),
+];
+
+/// One step of an iterative resolve: the server that was asked, its answer,
+/// and how many bytes the round trip cost.
+#[derive(Debug, Clone)]
+pub struct Hop {
+    pub server: String,
+    pub message: Message,
+    pub sent_len: usize,
+    pub rcvd_len: usize,
+}
+
+/// Walks the delegation chain for `domain`/`qtype` starting from the root
+/// servers, following NS referrals until an authoritative answer (or the hop
+/// limit) is reached.
+pub async fn trace(domain: &str, qtype: QType) -> Result<Vec<Hop>> {
+    let mut budget = MAX_HOPS;
+    trace_with_budget(domain, qtype, &mut budget).await
+}
+
+/// Does the actual work of `trace`, decrementing a hop budget shared with any
+/// nested `resolve_nameserver` -> `trace` calls needed to resolve missing
+/// glue, so the *total* work across the whole resolve is bounded by
+/// `MAX_HOPS`, not just a single hop loop.
+async fn trace_with_budget(domain: &str, qtype: QType, budget: &mut usize) -> Result<Vec<Hop>> {
+    let mut server = String::new();
+    let mut hops = Vec::new();
+    let mut at_root = true;
+
+    while *budget > 0 {
+        *budget -= 1;
+
+        let query = Message::new(domain, qtype).serialize()?;
+
+        let reply = if at_root {
+            let (chosen, reply) = query_root(&query).await?;
+            server = chosen;
+            at_root = false;
+            reply
+        } else {
+            transport::query(&query, &server, false).await?
+        };
+
+        let mut buffer = Buffer {
+            current: &reply,
+            source: &reply,
+        };
+        let message = Message::deserialize(&mut buffer)?;
+
+        let hop = Hop {
+            server: server.clone(),
+            message: message.clone(),
+            sent_len: query.len(),
+            rcvd_len: reply.len(),
+        };
+
+        let an_count = message.header.an_count;
+        hops.push(hop);
+
+        if an_count > 0 {
+            return Ok(hops);
+        }
+
+        let Some(next_name) = next_nameserver(&message) else {
+            return Err(anyhow!("{server} returned no answer and no NS referral"));
+        };
+
+        let next_ip = match glue_address(&message, &next_name) {
+            Some(ip) => ip,
+            None => resolve_nameserver(&next_name, budget).await?,
+        };
+
+        server = format_server(next_ip);
+    }
+
+    Err(anyhow!("delegation chain exceeded {MAX_HOPS} hops"))
+}
+
+/// Sends `query` to each root server in turn, returning the first reply that
+/// comes back successfully along with the server that answered. A root whose
+/// query fails with an error is skipped and the next one tried, up to all 13.
+/// There is no per-server timeout, so a root that never replies is waited on
+/// indefinitely.
+async fn query_root(query: &[u8]) -> Result<(String, Vec<u8>)> {
+    let mut last_err = None;
+
+    for ip in ROOT_SERVERS {
+        let server = format!("{ip}:53");
+        match transport::query(query, &server, false).await {
+            Ok(reply) => return Ok((server, reply)),
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| anyhow!("no root servers configured")))
+}
+
+/// Appends the standard DNS port to an address, bracketing ipv6 literals.
+fn format_server(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(ip) => format!("{ip}:53"),
+        IpAddr::V6(ip) => format!("[{ip}]:53"),
+    }
+}
+
+fn next_nameserver(message: &Message) -> Option<String> {
+    message.authorities.iter().find_map(|record| match &record.rdata {
+        RData::NS(name) => Some(name.clone()),
+        _ => None,
+    })
+}
+
+/// Looks for A or AAAA glue for `name` in the additional section, preferring
+/// whichever family shows up first so an ipv6-only nameserver doesn't force a
+/// needless recursive resolve.
+fn glue_address(message: &Message, name: &str) -> Option<IpAddr> {
+    message.additionals.iter().find_map(|record| match &record.rdata {
+        RData::A(ip) if record.name == name => Some(IpAddr::V4(*ip)),
+        RData::AAAA(ip) if record.name == name => Some(IpAddr::V6(*ip)),
+        _ => None,
+    })
+}
+
+/// No glue was offered for a delegated nameserver, so resolve its address the
+/// same way: trace it from the roots, spending from the same hop `budget` as
+/// the resolve that needed it.
+fn resolve_nameserver<'a>(
+    name: &'a str,
+    budget: &'a mut usize,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<IpAddr>> + Send + 'a>> {
+    Box::pin(async move {
+        let hops = trace_with_budget(name, QType::A, budget).await?;
+        hops.last()
+            .and_then(|hop| {
+                hop.message.records.iter().find_map(|record| match &record.rdata {
+                    RData::A(ip) => Some(IpAddr::V4(*ip)),
+                    RData::AAAA(ip) => Some(IpAddr::V6(*ip)),
+                    _ => None,
+                })
+            })
+            .ok_or_else(|| anyhow!("could not resolve an address for nameserver {name}"))
+    })
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+/// Bit mask for the TC (truncation) flag, the second-lowest bit of the first
+/// flags octet (byte 2) in the DNS header.
+const TC_MASK: u8 = 0x02;
+
+/// Sends `message` to `server` and returns the raw reply bytes, transparently
+/// retrying over TCP when the UDP reply comes back truncated or when
+/// `force_tcp` is set.
+pub async fn query(message: &[u8], server: &str, force_tcp: bool) -> Result<Vec<u8>> {
+    if force_tcp {
+        return send_tcp(message, server).await;
+    }
+
+    let reply = send_udp(message, server).await?;
+    if is_truncated(&reply) {
+        return send_tcp(message, server).await;
+    }
+
+    Ok(reply)
+}
+
+fn is_truncated(reply: &[u8]) -> bool {
+    reply.get(2).is_some_and(|flags| flags & TC_MASK != 0)
+}
+
+async fn send_udp(message: &[u8], server: &str) -> Result<Vec<u8>> {
+    let sock = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .context("could not bind")?;
+
+    sock.send_to(message, server)
+        .await
+        .context("failed to send query over udp")?;
+
+    let mut buffer = vec![0; 4096];
+    let (len, _) = sock
+        .recv_from(&mut buffer)
+        .await
+        .context("failed to receive response over udp")?;
+    buffer.truncate(len);
+
+    Ok(buffer)
+}
+
+/// Re-issues `message` over a TCP connection to `server`, using the
+/// mandatory 2-byte big-endian length prefix required by RFC 1035 section 4.2.2.
+async fn send_tcp(message: &[u8], server: &str) -> Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .context("could not connect over tcp")?;
+
+    let len = u16::try_from(message.len()).context("query too large to frame over tcp")?;
+    stream.write_all(&len.to_be_bytes()).await?;
+    stream.write_all(message).await?;
+
+    let mut len_buf = [0; 2];
+    stream
+        .read_exact(&mut len_buf)
+        .await
+        .context("failed to read tcp length prefix")?;
+    let reply_len = u16::from_be_bytes(len_buf) as usize;
+
+    let mut reply = vec![0; reply_len];
+    stream
+        .read_exact(&mut reply)
+        .await
+        .context("failed to read tcp response")?;
+
+    Ok(reply)
+}
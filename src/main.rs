@@ -1,5 +1,6 @@
 use std::{
     io::{self, Stdout},
+    net::IpAddr,
     process,
     time::{Duration, Instant},
 };
@@ -10,19 +11,20 @@ use anyhow::{anyhow, Context, Result};
 use clap::{Parser, Subcommand};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
 use dns::{message::Message, DeSerialize, Serialize};
-use tokio::net::UdpSocket;
 use validation::{check_length, check_token_length};
 
 use crate::dns::Buffer;
 use ratatui::{prelude::*, widgets::*};
 mod dns;
+mod system_resolver;
 mod validation;
 
 const TOP_BLOCK_SIZE: u16 = 1;
 const HEADER_BLOCK_SIZE: u16 = 5;
 const QUESTION_BLOCK_SIZE: u16 = 2;
 const MESSAGE_BLOCK_SIZE: u16 = 2;
-const STAT_BLOCK_SIZE: u16 = 6;
+const TRACE_BLOCK_SIZE: u16 = 2;
+const STAT_BLOCK_SIZE: u16 = 8;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -31,6 +33,10 @@ struct Statistics {
     pub msg_sent: usize,
     pub msg_rcvd: usize,
     pub current_time: DateTime<Local>,
+    pub server: String,
+    pub edns: Option<dns::message::EdnsOpt>,
+    /// One line per hop when resolved with `--trace`, empty otherwise.
+    pub trace: Vec<String>,
 }
 
 #[derive(Subcommand)]
@@ -49,6 +55,17 @@ pub enum Commands {
     MX { domain: String },
     #[command(long_about = "fetch SOA records")]
     SOA { domain: String },
+    #[command(long_about = "fetch SRV records")]
+    SRV { domain: String },
+    #[command(long_about = "fetch CAA records")]
+    CAA { domain: String },
+    #[command(
+        long_about = "run a local proxy that logs every query it receives and forwards it to the upstream resolver"
+    )]
+    Listen {
+        #[arg(help = "address to bind the proxy to, e.g. 0.0.0.0:53")]
+        bind_addr: String,
+    },
 }
 
 #[derive(Parser)]
@@ -67,59 +84,164 @@ struct Cli {
 
     #[arg(short, long = "raw-records")]
     raw: bool,
+
+    #[arg(long, help = "force the query over tcp instead of udp")]
+    tcp: bool,
+
+    #[arg(
+        long,
+        help = "resolve iteratively from the root servers instead of asking the upstream resolver directly"
+    )]
+    trace: bool,
+
+    #[arg(
+        short = 'x',
+        long,
+        value_name = "ADDRESS",
+        help = "reverse lookup (PTR) for an ipv4 or ipv6 literal"
+    )]
+    reverse: Option<IpAddr>,
+
+    #[arg(
+        long,
+        value_name = "ip[:port]",
+        help = "dns server to query, defaults to the system resolver"
+    )]
+    server: Option<String>,
+
+    #[arg(long, help = "set the DNSSEC OK (DO) bit on the EDNS(0) query")]
+    dnssec: bool,
+}
+
+const EDNS_UDP_SIZE: u16 = 4096;
+
+const DEFAULT_PORT: u16 = 53;
+
+/// Appends the default DNS port to a bare ip literal, leaving an address
+/// that already specifies a port untouched.
+fn with_default_port(server: &str) -> String {
+    match server.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => format!("{ip}:{DEFAULT_PORT}"),
+        Ok(IpAddr::V6(ip)) => format!("[{ip}]:{DEFAULT_PORT}"),
+        Err(_) => server.to_owned(),
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    let m = match &cli.command {
-        Some(Commands::Txt { domain }) => Message::txt(valid(domain)),
-        Some(Commands::Cname { domain }) => Message::cname(valid(domain)),
-        Some(Commands::A { domain }) => Message::a(valid(domain)),
-        Some(Commands::AAAA { domain }) => Message::aaaa(valid(domain)),
-        Some(Commands::NS { domain }) => Message::ns(valid(domain)),
-        Some(Commands::MX { domain }) => Message::mx(valid(domain)),
-        Some(Commands::SOA { domain }) => Message::soa(valid(domain)),
-        None => {
-            if let Some(address) = &cli.domain {
-                Message::a(valid(address))
-            } else {
-                eprintln!("You must supply a valid address as a first argument");
-                process::exit(1);
+    if let Some(Commands::Listen { bind_addr }) = &cli.command {
+        let upstream = with_default_port(&cli.server.unwrap_or_else(system_resolver::discover));
+        return dns::proxy::listen(bind_addr, &upstream)
+            .await
+            .context("proxy failed");
+    }
+
+    let m = if let Some(ip) = cli.reverse {
+        Message::ptr(&arpa_name(ip))
+    } else {
+        match &cli.command {
+            Some(Commands::Txt { domain }) => Message::txt(valid(domain)),
+            Some(Commands::Cname { domain }) => Message::cname(valid(domain)),
+            Some(Commands::A { domain }) => Message::a(valid(domain)),
+            Some(Commands::AAAA { domain }) => Message::aaaa(valid(domain)),
+            Some(Commands::NS { domain }) => Message::ns(valid(domain)),
+            Some(Commands::MX { domain }) => Message::mx(valid(domain)),
+            Some(Commands::SOA { domain }) => Message::soa(valid(domain)),
+            Some(Commands::SRV { domain }) => Message::srv(valid(domain)),
+            Some(Commands::CAA { domain }) => Message::caa(valid(domain)),
+            Some(Commands::Listen { .. }) => unreachable!("handled above"),
+            None => {
+                if let Some(address) = &cli.domain {
+                    Message::a(valid(address))
+                } else {
+                    eprintln!("You must supply a valid address as a first argument");
+                    process::exit(1);
+                }
             }
         }
     };
 
-    let sock = UdpSocket::bind("0.0.0.0:8080")
-        .await
-        .context("could not bind")?;
+    let m = m.with_edns(EDNS_UDP_SIZE, cli.dnssec);
+
+    let server = with_default_port(&cli.server.unwrap_or_else(system_resolver::discover));
+
+    let (message, stats) = if cli.trace {
+        let start = Instant::now();
+        let hops = dns::resolver::trace(&m.question.qname, m.question.qtype)
+            .await
+            .context("trace resolution failed")?;
+        let elapsed = start.elapsed();
+
+        let trace = hops
+            .iter()
+            .map(|hop| {
+                format!(
+                    "{}\tan={} ns={} ar={}",
+                    hop.server,
+                    hop.message.header.an_count,
+                    hop.message.header.ns_count,
+                    hop.message.header.ar_count
+                )
+            })
+            .collect();
+
+        let final_server = hops.last().map(|hop| hop.server.clone()).unwrap_or_default();
+        let msg_sent = hops.iter().map(|hop| hop.sent_len).sum();
+        let msg_rcvd = hops.iter().map(|hop| hop.rcvd_len).sum();
+        let message = hops
+            .into_iter()
+            .last()
+            .context("trace produced no hops")?
+            .message;
+
+        let stats = Statistics {
+            query_time: elapsed,
+            msg_sent,
+            msg_rcvd,
+            current_time: Local::now(),
+            server: final_server,
+            edns: message.negotiated_edns(),
+            trace,
+        };
+        (message, stats)
+    } else {
+        let m = m.serialize().context("Failed to serialize request")?;
 
-    let m = m.serialize().context("Failed to serialize request")?;
+        let start = Instant::now();
+        let reply = dns::transport::query(&m, &server, cli.tcp)
+            .await
+            .context("query failed")?;
+        let elapsed = start.elapsed();
 
-    let mut buffer = [0; 1024];
-    let start = Instant::now();
-    let _len = sock.send_to(&m, "1.1.1.1:53").await?;
-    let (msg_length, _) = sock.recv_from(&mut buffer).await?;
-    let elapsed = start.elapsed();
+        let mut buffer = Buffer {
+            current: &reply,
+            source: &reply,
+        };
 
-    let mut buffer = Buffer {
-        current: &buffer,
-        source: &buffer,
+        let message =
+            Message::deserialize(&mut buffer).context("Failed to deserialize response")?;
+
+        let stats = Statistics {
+            query_time: elapsed,
+            msg_sent: m.len(),
+            msg_rcvd: reply.len(),
+            current_time: Local::now(),
+            server: server.clone(),
+            edns: message.negotiated_edns(),
+            trace: Vec::new(),
+        };
+        (message, stats)
     };
 
-    let (_buffer, message) =
-        Message::deserialize(&mut buffer).context("Failed to deserialize response")?;
-
-    let stats = Statistics {
-        query_time: elapsed,
-        msg_sent: m.len(),
-        msg_rcvd: msg_length,
-        current_time: Local::now(),
-    };
     if !cli.raw {
-        let mut terminal = setup_terminal(message.header.qd_count, message.header.an_count)
-            .context("setup failed")?;
+        let mut terminal = setup_terminal(
+            message.header.qd_count,
+            message.header.an_count,
+            stats.trace.len() as u16,
+        )
+        .context("setup failed")?;
         terminal.draw(|f| render_app(f, &message, &stats))?;
         disable_raw_mode().context("failed to disable raw mode")?;
         let _ = terminal.show_cursor().context("unable to show cursor");
@@ -132,6 +254,25 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Builds the `in-addr.arpa`/`ip6.arpa` query name for a reverse (PTR) lookup.
+fn arpa_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{d}.{c}.{b}.{a}.in-addr.arpa")
+        }
+        IpAddr::V6(v6) => {
+            let nibbles: Vec<String> = v6
+                .octets()
+                .iter()
+                .rev()
+                .flat_map(|byte| [format!("{:x}", byte & 0x0f), format!("{:x}", byte >> 4)])
+                .collect();
+            format!("{}.ip6.arpa", nibbles.join("."))
+        }
+    }
+}
+
 fn valid(address: &String) -> &str {
     match validate(address) {
         Ok(address) => address,
@@ -161,13 +302,24 @@ fn validate(address: &String) -> Result<&str> {
     Ok(value)
 }
 
-fn setup_terminal(qd_count: u16, an_count: u16) -> Result<Terminal<CrosstermBackend<Stdout>>> {
+fn setup_terminal(
+    qd_count: u16,
+    an_count: u16,
+    trace_count: u16,
+) -> Result<Terminal<CrosstermBackend<Stdout>>> {
+    let trace_block_size = if trace_count > 0 {
+        TRACE_BLOCK_SIZE + trace_count
+    } else {
+        0
+    };
+
     let viewport_size = TOP_BLOCK_SIZE
         + HEADER_BLOCK_SIZE
         + QUESTION_BLOCK_SIZE
         + qd_count
         + MESSAGE_BLOCK_SIZE
         + an_count
+        + trace_block_size
         + STAT_BLOCK_SIZE;
 
     let stdout = io::stdout();
@@ -187,6 +339,12 @@ fn render_app(frame: &mut Frame, message: &Message, stats: &Statistics) {
         .constraints(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(frame.size());
 
+    let trace_block_size = if stats.trace.is_empty() {
+        0
+    } else {
+        TRACE_BLOCK_SIZE + stats.trace.len() as u16
+    };
+
     let inner = Layout::default()
         .direction(Direction::Vertical)
         .constraints(vec![
@@ -194,6 +352,7 @@ fn render_app(frame: &mut Frame, message: &Message, stats: &Statistics) {
             Constraint::Length(HEADER_BLOCK_SIZE),
             Constraint::Length(QUESTION_BLOCK_SIZE + message.header.qd_count),
             Constraint::Length(MESSAGE_BLOCK_SIZE + message.header.an_count),
+            Constraint::Length(trace_block_size),
             Constraint::Length(STAT_BLOCK_SIZE),
         ])
         .split(outer[0]);
@@ -268,6 +427,20 @@ fn render_app(frame: &mut Frame, message: &Message, stats: &Statistics) {
                 expire,
                 minimum,
             } => format!("{mname} {rname} {serial} {refresh} {retry} {expire} {minimum}"),
+            dns::record::RData::PTR(name) => name.to_string(),
+            dns::record::RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => format!("{priority} {weight} {port} {target}"),
+            dns::record::RData::CAA { flags, tag, value } => format!("{flags} {tag} {value}"),
+            dns::record::RData::OPT { udp_size, flags } => {
+                format!("udp_size={udp_size} do={}", flags & dns::record::DO_BIT != 0)
+            }
+            dns::record::RData::Unknown { qtype, data } => {
+                format!("TYPE{qtype} {}", dns::record::to_hex(data))
+            }
         };
 
         Row::new(vec![
@@ -296,6 +469,20 @@ fn render_app(frame: &mut Frame, message: &Message, stats: &Statistics) {
         ]);
     frame.render_widget(record_table, inner[3]);
 
+    // Trace (only present for --trace resolves)
+    if !stats.trace.is_empty() {
+        let trace_lines: Vec<Line> = stats.trace.iter().map(|hop| Line::from(hop.clone())).collect();
+        let t = Paragraph::new(trace_lines)
+            .block(
+                Block::new()
+                    .title("Trace")
+                    .borders(Borders::ALL)
+                    .fg(Color::Green),
+            )
+            .fg(Color::White);
+        frame.render_widget(t, inner[4]);
+    }
+
     let query_time = Line::from(vec![
         "Query time:".into(),
         " ".into(),
@@ -330,7 +517,26 @@ fn render_app(frame: &mut Frame, message: &Message, stats: &Statistics) {
         "bytes".into(),
     ]);
 
-    let t = Paragraph::new(vec![query_time, current_time, message_sent, message_rcvd])
+    let server = Line::from(vec!["Server:".into(), " ".into(), stats.server.clone().into()]);
+
+    let edns = Line::from(vec![
+        "EDNS:".into(),
+        " ".into(),
+        match stats.edns {
+            Some(edns) => format!("udp_size={} do={}", edns.udp_size, edns.dnssec),
+            None => "none".to_owned(),
+        }
+        .into(),
+    ]);
+
+    let t = Paragraph::new(vec![
+        query_time,
+        current_time,
+        message_sent,
+        message_rcvd,
+        server,
+        edns,
+    ])
         .block(
             Block::new()
                 .title("Statistics")
@@ -338,5 +544,27 @@ fn render_app(frame: &mut Frame, message: &Message, stats: &Statistics) {
                 .fg(Color::Green),
         )
         .fg(Color::White);
-    frame.render_widget(t, inner[4]);
+    frame.render_widget(t, inner[5]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arpa_name_reverses_ipv4_octets() {
+        let ip: IpAddr = "192.0.2.1".parse().unwrap();
+
+        assert_eq!(arpa_name(ip), "1.2.0.192.in-addr.arpa");
+    }
+
+    #[test]
+    fn arpa_name_reverses_ipv6_nibbles() {
+        let ip: IpAddr = "2001:db8::1".parse().unwrap();
+
+        assert_eq!(
+            arpa_name(ip),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+    }
 }
@@ -0,0 +1,41 @@
+use std::fs;
+
+/// Used when the system has no usable nameserver configuration.
+const FALLBACK: &str = "1.1.1.1";
+
+/// Reads the first `nameserver` line out of `/etc/resolv.conf` to find the
+/// platform's configured resolver. Falls back to a well-known public
+/// resolver when the file is missing, empty, or has no `nameserver` entries
+/// (for instance on non-Unix platforms).
+pub fn discover() -> String {
+    fs::read_to_string("/etc/resolv.conf")
+        .ok()
+        .and_then(|contents| first_nameserver(&contents))
+        .unwrap_or_else(|| FALLBACK.to_owned())
+}
+
+fn first_nameserver(contents: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let address = line.trim().strip_prefix("nameserver")?.trim();
+        (!address.is_empty()).then(|| address.to_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_first_nameserver_line() {
+        let contents = "# generated by NetworkManager\nnameserver 1.1.1.1\nnameserver 9.9.9.9\n";
+
+        assert_eq!(first_nameserver(contents), Some("1.1.1.1".to_owned()));
+    }
+
+    #[test]
+    fn returns_none_without_a_nameserver_line() {
+        let contents = "search example.com\noptions timeout:1\n";
+
+        assert_eq!(first_nameserver(contents), None);
+    }
+}
@@ -0,0 +1,19 @@
+const MAX_ADDRESS_LENGTH: usize = 255;
+const MAX_TOKEN_LENGTH: usize = 63;
+
+/// Checks that the whole domain does not exceed the 255 octet DNS limit.
+pub fn check_length(address: &str) -> bool {
+    address.len() <= MAX_ADDRESS_LENGTH
+}
+
+/// Checks that every dot-separated label of `address` fits within the 63
+/// octet DNS limit. Returns `(address, true)` when every label is valid, or
+/// `(offending_token, false)` for the first label that is too long.
+pub fn check_token_length(address: &str) -> (&str, bool) {
+    for token in address.split('.') {
+        if token.len() > MAX_TOKEN_LENGTH {
+            return (token, false);
+        }
+    }
+    (address, true)
+}